@@ -0,0 +1,119 @@
+use anyhow::{bail, Result};
+use ethers::core::types::{Address, U256};
+use ethers::providers::Quorum;
+use std::{env, str::FromStr, time::Duration};
+
+/// Runtime configuration for [`crate::VaultIndexer`], built from environment
+/// variables so the transport (pubsub vs. polling) can be picked without
+/// touching call sites.
+#[derive(Debug, Clone)]
+pub struct VaultConfig {
+    pub rpc_url: String,
+    pub vault_address: Address,
+    /// How often to call `eth_getFilterChanges` when polling. Ignored for
+    /// `wss://` endpoints, which use push-based subscriptions instead.
+    pub poll_interval: Duration,
+    /// Private key for the write path (harvest/rebalance/redeem). When unset
+    /// the indexer stays read-only.
+    pub private_key: Option<String>,
+    /// HTTP RPC URL used for submitting transactions. Defaults to `rpc_url`
+    /// when that's already an `http(s)://` endpoint; required separately when
+    /// the read path is a `wss://` subscription.
+    pub write_rpc_url: Option<String>,
+    /// Trigger a `harvest()` once indexed `total_assets` reaches this
+    /// threshold. Ignored when no writer is configured.
+    pub harvest_asset_threshold: Option<U256>,
+    /// Trigger a `rebalance()` at most once per this interval. Ignored when
+    /// no writer is configured.
+    pub rebalance_interval: Option<Duration>,
+    /// Additional `http(s)://` backends to read state through alongside
+    /// `rpc_url`. When non-empty, state reads go through a `QuorumProvider`
+    /// spanning these (plus `rpc_url` when it is itself `http(s)://`; a
+    /// `wss://` `rpc_url` is not part of the quorum), and only agreeing
+    /// responses are accepted; composes with the WS/HTTP transport choice
+    /// above, which still governs how new logs are discovered.
+    pub quorum_rpc_urls: Vec<String>,
+    pub quorum: Quorum,
+    pub store: StoreConfig,
+}
+
+/// Which `Store` implementation to persist events and state to.
+#[derive(Debug, Clone)]
+pub enum StoreConfig {
+    /// Append-only NDJSON log under `dir`.
+    Ndjson { dir: String },
+    /// Embedded SQLite database at `path`.
+    Sqlite { path: String },
+}
+
+impl VaultConfig {
+    pub fn from_env() -> Result<Self> {
+        let rpc_url = env::var("RPC_URL")
+            .or_else(|_| env::var("WSS_URL"))
+            .expect("RPC_URL (or WSS_URL) must be set");
+        let vault_address = env::var("VAULT_ADDRESS").expect("VAULT_ADDRESS must be set");
+        let poll_interval_secs: u64 = env::var("POLL_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(4);
+
+        Ok(Self {
+            rpc_url,
+            vault_address: Address::from_str(&vault_address)?,
+            poll_interval: Duration::from_secs(poll_interval_secs),
+            private_key: env::var("PRIVATE_KEY").ok(),
+            write_rpc_url: env::var("WRITE_RPC_URL").ok(),
+            harvest_asset_threshold: env::var("HARVEST_ASSET_THRESHOLD")
+                .ok()
+                .and_then(|v| U256::from_dec_str(&v).ok()),
+            rebalance_interval: env::var("REBALANCE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs),
+            quorum_rpc_urls: env::var("QUORUM_RPC_URLS")
+                .map(|v| v.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect())
+                .unwrap_or_default(),
+            quorum: match env::var("QUORUM_POLICY").as_deref() {
+                Ok("all") => Quorum::All,
+                Ok(n) if n.starts_with("at-least:") => {
+                    Quorum::ProviderCount(n["at-least:".len()..].parse().unwrap_or(1))
+                }
+                _ => Quorum::Majority,
+            },
+            store: match env::var("STORE_BACKEND").as_deref() {
+                Ok("sqlite") => StoreConfig::Sqlite {
+                    path: env::var("STORE_PATH").unwrap_or_else(|_| "data/vault-indexer.sqlite3".to_string()),
+                },
+                _ => StoreConfig::Ndjson {
+                    dir: env::var("STORE_PATH").unwrap_or_else(|_| "data".to_string()),
+                },
+            },
+        })
+    }
+
+    /// Whether state reads should go through a multi-backend `QuorumProvider`
+    /// rather than a single RPC endpoint.
+    pub fn is_quorum(&self) -> bool {
+        !self.quorum_rpc_urls.is_empty()
+    }
+
+    /// The HTTP RPC URL to submit write transactions against, if a signer is
+    /// configured at all.
+    pub fn write_rpc_url(&self) -> Option<&str> {
+        self.write_rpc_url
+            .as_deref()
+            .or_else(|| (!self.is_pubsub().unwrap_or(true)).then_some(self.rpc_url.as_str()))
+    }
+
+    /// Whether this config should use push-based subscriptions rather than
+    /// polling, based on the RPC URL scheme.
+    pub fn is_pubsub(&self) -> Result<bool> {
+        if self.rpc_url.starts_with("wss://") || self.rpc_url.starts_with("ws://") {
+            Ok(true)
+        } else if self.rpc_url.starts_with("http://") || self.rpc_url.starts_with("https://") {
+            Ok(false)
+        } else {
+            bail!("unsupported RPC URL scheme: {}", self.rpc_url)
+        }
+    }
+}
@@ -1,195 +1,554 @@
+mod config;
+mod reorg;
+mod store;
+mod stream;
+mod writer;
+
 use anyhow::Result;
 use ethers::{
-    contract::Contract,
-    core::{types::*, utils::keccak256},
-    providers::{Provider, Ws, Middleware, StreamExt},
+    contract::{abigen, EthEvent},
+    core::types::*,
+    middleware::{gas_oracle::GeoMeanGasOracle, GasOracleMiddleware, NonceManagerMiddleware, SignerMiddleware},
+    providers::{Http, Middleware, Provider, QuorumProvider, StreamExt, WeightedProvider, Ws},
+    signers::{LocalWallet, Signer},
 };
 use serde::{Deserialize, Serialize};
-use std::{env, sync::Arc, str::FromStr};
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
 
-// ERC-4626 specific events
-#[derive(Debug, Serialize, Deserialize)]
+use config::{StoreConfig, VaultConfig};
+use reorg::{BlockMeta, ReorgTracker, Update};
+use store::{ndjson::NdjsonStore, sqlite::SqliteStore, Store};
+use stream::FilterPoller;
+use writer::{VaultWriter, WriteMiddleware};
+
+/// How many recent blocks we keep header hashes for, bounding how deep a
+/// reorg we can reconcile without a full backfill.
+const REORG_WINDOW: usize = 64;
+
+// Generates typed bindings (including `EthEvent` impls for every event in the
+// ABI) from the ERC-4626 vault interface, so log decoding goes through
+// `abigen!`-derived `EthLogDecode` instead of hand-matched topic hashes.
+abigen!(
+    Vault,
+    "../abi/vault.json",
+    event_derives(serde::Deserialize, serde::Serialize)
+);
+
+// Strongly-typed vault events we care about, decoded via the generated
+// `EthEvent::decode_log` impls (`DepositFilter`, `WithdrawFilter`, `TransferFilter`),
+// tagged with the log metadata needed to revert them if their block is later
+// orphaned by a reorg.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct VaultEvent {
-    event_type: String,
+    kind: VaultEventKind,
     block_number: u64,
-    transaction_hash: String,
-    sender: Address,
-    receiver: Option<Address>,
-    owner: Option<Address>,
-    assets: U256,
-    shares: U256,
-    timestamp: u64,
+    block_hash: H256,
+    transaction_hash: H256,
+    log_index: U256,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum VaultEventKind {
+    Deposit(DepositFilter),
+    Withdraw(WithdrawFilter),
+    Transfer(TransferFilter),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct VaultState {
     total_assets: U256,
     total_supply: U256,
     block_number: u64,
+    block_hash: H256,
     timestamp: u64,
 }
 
-struct VaultIndexer {
-    provider: Arc<Provider<Ws>>,
-    contract: Contract<Provider<Ws>>,
+/// Marker written when a previously saved event or state snapshot is reverted
+/// because its block was orphaned by a reorg.
+#[derive(Debug, Serialize, Deserialize)]
+struct RevertedBlock {
+    block_number: u64,
+    block_hash: H256,
+}
+
+/// The live connection to the node driving log discovery: push-based
+/// subscriptions over WebSockets, or a polling fallback over HTTP. Picked in
+/// `VaultIndexer::new` from the RPC URL scheme alone — whether state reads
+/// additionally go through a `QuorumProvider` is an orthogonal concern, see
+/// `state_reader`.
+///
+/// `Polling` is generic over `M: Middleware` (rather than hard-coded to
+/// `Provider<Http>`) so the same signer-wrapped stack used for the write path
+/// (`SignerMiddleware` → `NonceManagerMiddleware` → `GasOracleMiddleware`) can
+/// double as the HTTP connection driving reads, instead of opening a second,
+/// unsigned one. `Pubsub` stays concrete: `subscribe_blocks`/`subscribe_logs`
+/// require a `PubsubClient`, which only `Provider<Ws>` implements here.
+enum Transport<M: Middleware> {
+    Pubsub {
+        provider: Arc<Provider<Ws>>,
+        contract: Vault<Provider<Ws>>,
+    },
+    Polling {
+        provider: Arc<M>,
+        contract: Vault<M>,
+        poll_interval: Duration,
+    },
+}
+
+/// Where `totalAssets`/`totalSupply` reads are sent. Single-backend mirrors
+/// whichever node `Transport` is already talking to; `Quorum` spans
+/// `quorum_rpc_urls` (plus `rpc_url` when it is `http(s)://`) and only accepts
+/// agreeing responses, independent of which transport is driving log
+/// discovery.
+enum StateReader {
+    Single,
+    Quorum(Vault<Provider<QuorumProvider<Http>>>),
+}
+
+struct VaultIndexer<M: Middleware + 'static> {
     vault_address: Address,
+    transport: Transport<M>,
+    state_reader: StateReader,
+    reorg: Mutex<ReorgTracker>,
+    store: Arc<dyn Store>,
+    /// Submits vault-maintenance transactions. `None` keeps the indexer
+    /// strictly read-only, which is the default when no signer is configured.
+    /// When the read path is HTTP it shares the polling transport's middleware
+    /// instance rather than opening a second connection (see
+    /// `Transport::Polling`); with a `wss://` read path it uses its own HTTP
+    /// connection to the configured write RPC URL.
+    writer: Option<VaultWriter<M>>,
+    harvest_asset_threshold: Option<U256>,
+    /// `total_assets` as of the last `maybe_harvest` call, used to fire only
+    /// on the below→above transition rather than on every block the
+    /// threshold stays crossed.
+    last_total_assets: Mutex<Option<U256>>,
+    rebalance_interval: Option<Duration>,
+    last_rebalance: Mutex<Option<Instant>>,
 }
 
-impl VaultIndexer {
-    async fn new(ws_url: &str, vault_address: &str, abi: &str) -> Result<Self> {
-        let provider = Provider::<Ws>::connect(ws_url).await?;
-        let provider = Arc::new(provider);
-        
-        let vault_address = Address::from_str(vault_address)?;
-        let abi = serde_json::from_str(abi)?;
-        let contract = Contract::new(vault_address, abi, provider.clone());
+impl<M: Middleware + 'static> VaultIndexer<M> {
+    /// `middleware` drives the `Polling` transport (and, when `write_capable`
+    /// is set, doubles as the write stack) and is required whenever
+    /// `config.is_pubsub()` is false — the `Pubsub` transport always connects
+    /// its own concrete `Provider<Ws>` regardless of `middleware`.
+    async fn new(config: VaultConfig, middleware: Option<Arc<M>>, write_capable: bool) -> Result<Self> {
+        let transport = if config.is_pubsub()? {
+            let provider = Arc::new(Provider::<Ws>::connect(&config.rpc_url).await?);
+            let contract = Vault::new(config.vault_address, provider.clone());
+            Transport::Pubsub { provider, contract }
+        } else {
+            let provider = middleware
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("polling transport requires a middleware instance"))?;
+            let contract = Vault::new(config.vault_address, provider.clone());
+            Transport::Polling {
+                provider,
+                contract,
+                poll_interval: config.poll_interval,
+            }
+        };
+
+        let state_reader = if config.is_quorum() {
+            // `rpc_url` only joins the quorum set when it's itself an
+            // `http(s)://` endpoint — a `wss://` read path can't serve
+            // `eth_call`s through an `Http` backend.
+            let rpc_url_is_http = !config.is_pubsub()?;
+            let backends = rpc_url_is_http
+                .then_some(config.rpc_url.as_str())
+                .into_iter()
+                .chain(config.quorum_rpc_urls.iter().map(String::as_str))
+                .map(|url| Http::from_str(url).map(WeightedProvider::new))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let quorum_provider = QuorumProvider::builder()
+                .add_providers(backends)
+                .quorum(config.quorum.clone())
+                .build();
+            let provider = Arc::new(Provider::new(quorum_provider));
+            StateReader::Quorum(Vault::new(config.vault_address, provider))
+        } else {
+            StateReader::Single
+        };
+
+        let writer = match (write_capable, &middleware) {
+            (true, Some(middleware)) => Some(VaultWriter::new(config.vault_address, middleware.clone())),
+            _ => None,
+        };
+        let store: Arc<dyn Store> = match &config.store {
+            StoreConfig::Ndjson { dir } => Arc::new(NdjsonStore::new(dir)?),
+            StoreConfig::Sqlite { path } => Arc::new(SqliteStore::open(path)?),
+        };
 
         Ok(Self {
-            provider,
-            contract,
-            vault_address,
+            vault_address: config.vault_address,
+            transport,
+            state_reader,
+            reorg: Mutex::new(ReorgTracker::new(REORG_WINDOW)),
+            store,
+            writer,
+            harvest_asset_threshold: config.harvest_asset_threshold,
+            last_total_assets: Mutex::new(None),
+            rebalance_interval: config.rebalance_interval,
+            last_rebalance: Mutex::new(None),
         })
     }
 
-    async fn get_vault_state(&self, block_number: u64) -> Result<VaultState> {
-        let block = self.provider.get_block(block_number).await?.unwrap();
-        
-        // Call ERC-4626 view functions
-        let total_assets: U256 = self.contract
-            .method::<_, U256>("totalAssets", ())?
-            .call()
-            .await?;
-
-        let total_supply: U256 = self.contract
-            .method::<_, U256>("totalSupply", ())?
-            .call()
-            .await?;
-
-        Ok(VaultState {
-            total_assets,
-            total_supply,
-            block_number,
-            timestamp: block.timestamp.as_u64(),
-        })
+    /// Backfills any gap between the last block we have persisted state for
+    /// and the current chain tip via `eth_getLogs`, so restarts don't lose
+    /// history between the last run and this one.
+    async fn backfill(&self) -> Result<()> {
+        let Some(last_processed) = self.store.last_processed_block().await? else {
+            return Ok(());
+        };
+
+        let (head, logs) = match &self.transport {
+            Transport::Pubsub { provider, .. } => backfill_logs(provider.as_ref(), self.vault_address, last_processed).await?,
+            Transport::Polling { provider, .. } => backfill_logs(provider.as_ref(), self.vault_address, last_processed).await?,
+        };
+
+        if !logs.is_empty() {
+            log::info!("backfilling {} log(s) from block {} to {head}", logs.len(), last_processed + 1);
+        }
+        for log in logs {
+            self.handle_log(&log).await?;
+        }
+
+        Ok(())
     }
 
-    fn parse_vault_event(&self, log: &Log, timestamp: u64) -> Result<Option<VaultEvent>> {
-        // Define event signatures
-        let deposit_sig = "Deposit(address,address,uint256,uint256)";
-        let withdraw_sig = "Withdraw(address,address,address,uint256,uint256)";
+    /// Submits a `harvest()` only on the block where indexed `total_assets`
+    /// crosses the configured threshold from below, not on every subsequent
+    /// block it stays crossed. The first state seen after startup counts as
+    /// coming from below, so it triggers a harvest if it is already at or
+    /// above the threshold. No-op when there's no writer or no threshold.
+    async fn maybe_harvest(&self, state: &VaultState) -> Result<()> {
+        let (Some(writer), Some(threshold)) = (&self.writer, self.harvest_asset_threshold) else {
+            return Ok(());
+        };
 
-        let topics = log.topics.iter()
-            .map(|t| t.as_bytes().to_vec())
-            .collect::<Vec<_>>();
+        let mut last_total_assets = self.last_total_assets.lock().await;
+        let was_below = match *last_total_assets {
+            Some(prev) => prev < threshold,
+            None => true,
+        };
+        *last_total_assets = Some(state.total_assets);
 
-        if topics.is_empty() {
-            return Ok(None);
+        if was_below && state.total_assets >= threshold {
+            log::info!("total_assets {} crossed harvest threshold {threshold}, harvesting", state.total_assets);
+            let tx_hash = writer.harvest().await?;
+            log::info!("harvest submitted: {tx_hash:?}");
         }
 
-        match topics[0].as_slice() {
-            sig if sig == keccak256(deposit_sig).as_ref() => {
-                let sender = Address::from_slice(&topics[1][12..]);
-                let owner = Address::from_slice(&topics[2][12..]);
-                let data = log.data.as_ref();
-                let assets = U256::from_big_endian(&data[..32]);
-                let shares = U256::from_big_endian(&data[32..]);
-
-                Ok(Some(VaultEvent {
-                    event_type: "Deposit".to_string(),
-                    block_number: log.block_number.unwrap().as_u64(),
-                    transaction_hash: format!("{:?}", log.transaction_hash.unwrap()),
-                    sender,
-                    receiver: Some(owner),
-                    owner: Some(owner),
-                    assets,
-                    shares,
-                    timestamp,
-                }))
-            },
-            sig if sig == keccak256(withdraw_sig).as_bytes() => {
-                let sender = Address::from_slice(&topics[1][12..]);
-                let receiver = Address::from_slice(&topics[2][12..]);
-                let owner = Address::from_slice(&topics[3][12..]);
-                let data = log.data.as_ref();
-                let assets = U256::from_big_endian(&data[..32]);
-                let shares = U256::from_big_endian(&data[32..]);
-
-                Ok(Some(VaultEvent {
-                    event_type: "Withdraw".to_string(), 
-                    block_number: log.block_number.unwrap().as_u64(),
-                    transaction_hash: format!("{:?}", log.transaction_hash.unwrap()),
-                    sender,
-                    receiver: Some(receiver),
-                    owner: Some(owner),
-                    assets,
-                    shares,
-                    timestamp,
-                }))
-            },
-            _ => Ok(None),
+        Ok(())
+    }
+
+    /// Submits a `rebalance()` at most once per `rebalance_interval`. No-op
+    /// when there's no writer or no interval configured.
+    async fn maybe_rebalance(&self) -> Result<()> {
+        let (Some(writer), Some(interval)) = (&self.writer, self.rebalance_interval) else {
+            return Ok(());
+        };
+
+        let mut last_rebalance = self.last_rebalance.lock().await;
+        if last_rebalance.is_some_and(|t| t.elapsed() < interval) {
+            return Ok(());
+        }
+
+        log::info!("rebalance interval elapsed, rebalancing");
+        let tx_hash = writer.rebalance().await?;
+        log::info!("rebalance submitted: {tx_hash:?}");
+        *last_rebalance = Some(Instant::now());
+
+        Ok(())
+    }
+
+    /// Reads vault state at the exact block that produced it, rather than
+    /// whatever block happens to be the tip when the call runs, so a log and
+    /// its accompanying state snapshot never race against a moving chain tip.
+    async fn get_vault_state(&self, block_number: u64, block_hash: H256) -> Result<VaultState> {
+        if let StateReader::Quorum(contract) = &self.state_reader {
+            return fetch_vault_state(contract.client().as_ref(), contract, block_number, block_hash).await;
+        }
+
+        match &self.transport {
+            Transport::Pubsub { provider, contract } => {
+                fetch_vault_state(provider, contract, block_number, block_hash).await
+            }
+            Transport::Polling { provider, contract, .. } => {
+                fetch_vault_state(provider, contract, block_number, block_hash).await
+            }
         }
     }
 
+    // Decodes a raw log into one of our known vault events using the
+    // `abigen!`-generated `EthLogDecode` implementations, which validate topic
+    // count and data length for us and return `None` for logs that don't match
+    // any known event (including anonymous/malformed ones) instead of panicking.
+    fn parse_vault_event(&self, log: &Log) -> Result<Option<VaultEvent>> {
+        let raw: RawLog = log.clone().into();
+
+        let kind = if let Ok(event) = DepositFilter::decode_log(&raw) {
+            VaultEventKind::Deposit(event)
+        } else if let Ok(event) = WithdrawFilter::decode_log(&raw) {
+            VaultEventKind::Withdraw(event)
+        } else if let Ok(event) = TransferFilter::decode_log(&raw) {
+            VaultEventKind::Transfer(event)
+        } else {
+            return Ok(None);
+        };
+
+        let (Some(block_number), Some(block_hash), Some(transaction_hash)) =
+            (log.block_number, log.block_hash, log.transaction_hash)
+        else {
+            log::debug!("Ignoring log missing block/tx metadata (likely pending): {log:?}");
+            return Ok(None);
+        };
+
+        Ok(Some(VaultEvent {
+            kind,
+            block_number: block_number.as_u64(),
+            block_hash,
+            transaction_hash,
+            log_index: log.log_index.unwrap_or_default(),
+        }))
+    }
+
     async fn run(&self) -> Result<()> {
+        self.backfill().await?;
+
+        match &self.transport {
+            Transport::Pubsub { provider, .. } => self.run_pubsub(provider).await,
+            Transport::Polling {
+                provider,
+                poll_interval,
+                ..
+            } => self.run_polling(provider, *poll_interval).await,
+        }
+    }
+
+    async fn run_pubsub(&self, provider: &Arc<Provider<Ws>>) -> Result<()> {
         // Subscribe to new blocks
-        let mut block_stream = self.provider.subscribe_blocks().await?;
-        
+        let mut block_stream = provider.subscribe_blocks().await?;
+
         // Subscribe to logs for the vault contract
         let filter = Filter::new()
             .address(self.vault_address)
             .from_block(BlockNumber::Latest);
-        let mut event_stream = self.provider.subscribe_logs(&filter).await?;
+        let mut event_stream = provider.subscribe_logs(&filter).await?;
 
-        log::info!("Starting real-time event monitoring...");
+        log::info!("Starting real-time event monitoring (pubsub)...");
 
         loop {
             tokio::select! {
                 // Handle new blocks
                 Some(block) = block_stream.next() => {
-                    log::info!("New block: {}", block.number.unwrap_or_default());
-                    
-                    // Get updated vault state on each new block
-                    if let Ok(state) = self.get_vault_state(block.number.unwrap().as_u64()).await {
+                    let (number, hash, parent_hash) = (
+                        block.number.unwrap().as_u64(),
+                        block.hash.unwrap(),
+                        block.parent_hash,
+                    );
+                    log::info!("New block: {number}");
+
+                    self.handle_new_head(provider.as_ref(), BlockMeta { number, hash, parent_hash }).await?;
+
+                    if let Ok(state) = self.get_vault_state(number, hash).await {
                         log::info!("Vault state updated: {:?}", state);
                         self.save_state(&state).await?;
+                        self.maybe_harvest(&state).await?;
+                        self.maybe_rebalance().await?;
                     }
                 }
-                
+
                 // Handle new logs
                 Some(log) = event_stream.next() => {
-                    let block = self.provider
-                        .get_block(log.block_number.unwrap())
-                        .await?
-                        .unwrap();
-                    
-                    if let Some(event) = self.parse_vault_event(&log, block.timestamp.as_u64())? {
-                        log::info!("New vault event: {:?}", event);
-                        self.save_event(&event).await?;
+                    self.handle_log(&log).await?;
+                }
+            }
+        }
+    }
+
+    async fn run_polling(&self, provider: &Arc<M>, poll_interval: Duration) -> Result<()> {
+        let filter = Filter::new().address(self.vault_address);
+        let mut last_head = provider.get_block_number().await?.as_u64();
+
+        let poller = FilterPoller::new(provider.clone(), filter, poll_interval);
+        let mut log_batches = Box::pin(poller.into_stream(last_head));
+
+        log::info!("Starting event monitoring via eth_getFilterChanges polling (every {poll_interval:?})...");
+
+        while let Some(logs) = log_batches.next().await {
+            for log in &logs {
+                self.handle_log(log).await?;
+            }
+
+            // Feed every header since the last poll into the reorg tracker —
+            // not just blocks that happened to carry a vault log — so the
+            // window stays populated enough for `hash_at` to catch a reorg.
+            let head = provider.get_block_number().await?.as_u64();
+            for number in (last_head + 1)..=head {
+                let Some(block) = provider.get_block(number).await? else {
+                    continue;
+                };
+                let Some(hash) = block.hash else { continue };
+                let meta = BlockMeta {
+                    number,
+                    hash,
+                    parent_hash: block.parent_hash,
+                };
+                self.handle_new_head(provider.as_ref(), meta).await?;
+
+                if number == head {
+                    if let Ok(state) = self.get_vault_state(meta.number, meta.hash).await {
+                        log::info!("Vault state updated: {:?}", state);
+                        self.save_state(&state).await?;
+                        self.maybe_harvest(&state).await?;
+                        self.maybe_rebalance().await?;
                     }
                 }
             }
+            last_head = last_head.max(head);
         }
+
+        Ok(())
     }
 
-    async fn save_event(&self, event: &VaultEvent) -> Result<()> {
-        let json = serde_json::to_string_pretty(event)?;
-        let timestamp = chrono::Utc::now().timestamp();
-        std::fs::write(
-            format!("data/event_{}.json", timestamp),
-            json,
-        )?;
+    /// Feeds a newly seen header into the reorg tracker and, if it turns out
+    /// to orphan blocks we'd already indexed, reverts what we saved for them
+    /// and re-fetches logs for the new canonical branch.
+    async fn handle_new_head<P: Middleware>(&self, provider: &P, incoming: BlockMeta) -> Result<()> {
+        let update = self
+            .reorg
+            .lock()
+            .await
+            .record(provider, incoming)
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        let Update::Reorged { common_ancestor, orphaned } = update else {
+            return Ok(());
+        };
+
+        log::warn!(
+            "reorg detected: {} block(s) orphaned, common ancestor at block {common_ancestor}",
+            orphaned.len()
+        );
+
+        for block in &orphaned {
+            self.revert_block(block.number, block.hash).await?;
+        }
+
+        // Re-fetch logs for the new canonical branch from the common
+        // ancestor up to (and including) the incoming head.
+        let filter = Filter::new()
+            .address(self.vault_address)
+            .from_block(common_ancestor + 1)
+            .to_block(incoming.number);
+        for log in provider.get_logs(&filter).await.map_err(|e| anyhow::anyhow!("{e}"))? {
+            self.handle_log(&log).await?;
+        }
+
         Ok(())
     }
 
-    async fn save_state(&self, state: &VaultState) -> Result<()> {
-        let json = serde_json::to_string_pretty(state)?;
-        let timestamp = chrono::Utc::now().timestamp();
-        std::fs::write(
-            format!("data/state_{}.json", timestamp),
-            json,
-        )?;
+    async fn revert_block(&self, block_number: u64, block_hash: H256) -> Result<()> {
+        log::warn!("reverting block {block_number} ({block_hash:?}), orphaned by reorg");
+        self.store.revert_block(&RevertedBlock { block_number, block_hash }).await
+    }
+
+    async fn handle_log(&self, log: &Log) -> Result<()> {
+        match self.parse_vault_event(log)? {
+            Some(event) => {
+                log::info!("New vault event: {:?}", event);
+                self.save_event(&event).await?;
+            }
+            None => log::debug!("Ignoring unrecognized log in tx {:?}", log.transaction_hash),
+        }
         Ok(())
     }
+
+    async fn save_event(&self, event: &VaultEvent) -> Result<()> {
+        self.store.append_event(event).await
+    }
+
+    async fn save_state(&self, state: &VaultState) -> Result<()> {
+        self.store.upsert_state(state).await
+    }
+}
+
+async fn backfill_logs<M: Middleware>(
+    provider: &M,
+    vault_address: Address,
+    last_processed_block: u64,
+) -> Result<(u64, Vec<Log>)> {
+    let head = provider.get_block_number().await.map_err(|e| anyhow::anyhow!("{e}"))?.as_u64();
+    if head <= last_processed_block {
+        return Ok((head, Vec::new()));
+    }
+
+    let filter = Filter::new()
+        .address(vault_address)
+        .from_block(last_processed_block + 1)
+        .to_block(head);
+    let logs = provider.get_logs(&filter).await.map_err(|e| anyhow::anyhow!("{e}"))?;
+    Ok((head, logs))
+}
+
+async fn fetch_vault_state<M: Middleware>(
+    provider: &M,
+    contract: &Vault<M>,
+    block_number: u64,
+    block_hash: H256,
+) -> Result<VaultState> {
+    let block = provider
+        .get_block(block_hash)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("node has no block for hash {block_hash:?} (block {block_number})"))?;
+
+    let total_assets = contract.total_assets().block(block_hash).call().await?;
+    let total_supply = contract.total_supply().block(block_hash).call().await?;
+
+    Ok(VaultState {
+        total_assets,
+        total_supply,
+        block_number,
+        block_hash,
+        timestamp: block.timestamp.as_u64(),
+    })
+}
+
+/// Builds the signer-backed write stack (signer → nonce manager → gas
+/// oracle) when a private key is configured. Returns `None` for a strictly
+/// read-only setup. The returned middleware also drives the `Polling`
+/// transport's reads in `main`, so a configured signer serves both paths off
+/// one connection instead of a second, unsigned one.
+async fn build_write_middleware(config: &VaultConfig) -> Result<Option<WriteMiddleware>> {
+    let Some(private_key) = &config.private_key else {
+        return Ok(None);
+    };
+
+    let Some(write_rpc_url) = config.write_rpc_url() else {
+        log::warn!(
+            "PRIVATE_KEY is set but no write RPC URL could be resolved (read path is a wss:// \
+             subscription and WRITE_RPC_URL is unset); staying read-only"
+        );
+        return Ok(None);
+    };
+
+    let provider = Provider::<Http>::try_from(write_rpc_url)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let wallet: LocalWallet = private_key.parse::<LocalWallet>()?.with_chain_id(chain_id);
+    let address = wallet.address();
+
+    let signer = SignerMiddleware::new(provider, wallet);
+    let nonce_managed = NonceManagerMiddleware::new(signer, address);
+    let stack = GasOracleMiddleware::new(nonce_managed, GeoMeanGasOracle::default());
+
+    Ok(Some(stack))
 }
 
 #[tokio::main]
@@ -197,13 +556,26 @@ async fn main() -> Result<()> {
     dotenv::dotenv().ok();
     env_logger::init();
 
-    // Use WSS URL instead of HTTP
-    let ws_url = env::var("WSS_URL").expect("WSS_URL must be set");
-    let vault_address = env::var("VAULT_ADDRESS").expect("VAULT_ADDRESS must be set");
-    let abi = include_str!("../../abi/vault.json");
+    let config = VaultConfig::from_env()?;
 
-    let indexer = VaultIndexer::new(&ws_url, &vault_address, abi).await?;
-    indexer.run().await?;
+    // Rust generics are resolved at compile time, so the two possible
+    // `VaultIndexer<M>` monomorphizations — signer-backed or plain HTTP — are
+    // dispatched explicitly here rather than selected through a trait object.
+    match build_write_middleware(&config).await? {
+        Some(middleware) => {
+            let indexer = VaultIndexer::new(config, Some(Arc::new(middleware)), true).await?;
+            indexer.run().await?;
+        }
+        None if config.is_pubsub()? => {
+            let indexer = VaultIndexer::<Provider<Http>>::new(config, None, false).await?;
+            indexer.run().await?;
+        }
+        None => {
+            let provider = Arc::new(Provider::<Http>::try_from(config.rpc_url.as_str())?);
+            let indexer = VaultIndexer::new(config, Some(provider), false).await?;
+            indexer.run().await?;
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,121 @@
+use ethers::core::types::H256;
+use ethers::providers::Middleware;
+use std::collections::VecDeque;
+
+/// The bits of a block header we need to detect a reorg: its own hash and the
+/// hash of the block it claims as its parent.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockMeta {
+    pub number: u64,
+    pub hash: H256,
+    pub parent_hash: H256,
+}
+
+#[derive(Debug)]
+pub enum Update {
+    /// The incoming header extends the chain we were tracking; nothing to
+    /// roll back.
+    Extended,
+    /// The incoming header reorgs blocks we'd already tracked.
+    /// `common_ancestor` is the height both the old and new branch still
+    /// agree on; everything above it in `orphaned` was on the losing branch
+    /// and needs its saved events/state reverted before re-indexing from
+    /// `common_ancestor + 1` on the new branch.
+    Reorged {
+        common_ancestor: u64,
+        orphaned: Vec<BlockMeta>,
+    },
+}
+
+/// Rolling window of the last `capacity` block headers we've processed, used
+/// to detect reorgs (an incoming header whose `parent_hash` doesn't match
+/// what we recorded for the previous height) and to find the common ancestor
+/// by walking the new branch's ancestry backwards through the node.
+pub struct ReorgTracker {
+    window: VecDeque<BlockMeta>,
+    capacity: usize,
+}
+
+impl ReorgTracker {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            window: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn hash_at(&self, number: u64) -> Option<H256> {
+        self.window.iter().find(|b| b.number == number).map(|b| b.hash)
+    }
+
+    fn push(&mut self, meta: BlockMeta) {
+        // A reorg can replace a block at a height we've already recorded (most
+        // commonly the tip itself); keep the window keyed by height rather than
+        // appending a second, stale entry alongside it.
+        if let Some(existing) = self.window.iter_mut().find(|b| b.number == meta.number) {
+            *existing = meta;
+            return;
+        }
+
+        if self.window.len() == self.capacity {
+            self.window.pop_front();
+        }
+        self.window.push_back(meta);
+    }
+
+    /// Records an incoming header, walking backwards through `provider` to
+    /// find the common ancestor if it reorgs blocks we already tracked.
+    pub async fn record<M: Middleware>(&mut self, provider: &M, incoming: BlockMeta) -> anyhow::Result<Update> {
+        let tip_reorged = matches!(self.hash_at(incoming.number), Some(hash) if hash != incoming.hash);
+        let parent_mismatch = matches!(
+            self.hash_at(incoming.number.saturating_sub(1)),
+            Some(expected_parent) if expected_parent != incoming.parent_hash
+        );
+
+        match tip_reorged || parent_mismatch {
+            true => {
+                let mut cursor_number = incoming.number.saturating_sub(1);
+                let mut cursor_hash = incoming.parent_hash;
+
+                // Walk the new branch's ancestry backwards until we reach a
+                // height whose hash matches what we already have tracked (the
+                // common ancestor), or we fall outside our window.
+                while cursor_number > 0 && self.hash_at(cursor_number) != Some(cursor_hash) {
+                    let Some(block) = provider
+                        .get_block(cursor_hash)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("{e}"))?
+                    else {
+                        break;
+                    };
+                    cursor_hash = block.parent_hash;
+                    cursor_number = cursor_number.saturating_sub(1);
+
+                    if self.window.front().map(|b| b.number) >= Some(cursor_number) {
+                        break;
+                    }
+                }
+
+                let common_ancestor = cursor_number;
+                let orphaned: Vec<BlockMeta> = self
+                    .window
+                    .iter()
+                    .filter(|b| b.number > common_ancestor)
+                    .cloned()
+                    .collect();
+
+                self.window.retain(|b| b.number <= common_ancestor);
+                self.push(incoming);
+
+                Ok(Update::Reorged {
+                    common_ancestor,
+                    orphaned,
+                })
+            }
+            _ => {
+                self.push(incoming);
+                Ok(Update::Extended)
+            }
+        }
+    }
+}
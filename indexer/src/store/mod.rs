@@ -0,0 +1,24 @@
+pub mod ndjson;
+pub mod sqlite;
+
+use crate::{RevertedBlock, VaultEvent, VaultState};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Persists indexed events and state snapshots, replacing the old one-file-
+/// per-event JSON dump so history is queryable and safe to write from
+/// concurrent callers.
+///
+/// `last_processed_block` is what lets the indexer backfill any gap via
+/// `eth_getLogs` on startup before switching to the live stream, giving
+/// gap-free history across restarts.
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn append_event(&self, event: &VaultEvent) -> Result<()>;
+    async fn upsert_state(&self, state: &VaultState) -> Result<()>;
+    async fn last_processed_block(&self) -> Result<Option<u64>>;
+    /// Records that a block was orphaned by a reorg and its saved events/state
+    /// are stale. The `Store` trait has no delete, so this is a breadcrumb for
+    /// operators/consumers rather than a retraction of the superseded rows.
+    async fn revert_block(&self, reverted: &RevertedBlock) -> Result<()>;
+}
@@ -0,0 +1,106 @@
+use super::Store;
+use crate::{RevertedBlock, VaultEvent, VaultState};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+use tokio::sync::Mutex;
+
+/// Append-only newline-delimited JSON log, one line per event or state
+/// snapshot, keyed by `(block_number, log_index)` so concurrent writers can't
+/// collide the way timestamp-named files used to.
+pub struct NdjsonStore {
+    events_path: PathBuf,
+    state_path: PathBuf,
+    reverts_path: PathBuf,
+    // Plain file appends aren't atomic across these files together, so we
+    // serialize writers here rather than relying on filesystem locking.
+    lock: Mutex<()>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EventRecord<'a> {
+    block_number: u64,
+    log_index: String,
+    event: &'a VaultEvent,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StateRecord<'a> {
+    block_number: u64,
+    state: &'a VaultState,
+}
+
+impl NdjsonStore {
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            events_path: dir.join("events.ndjson"),
+            state_path: dir.join("state.ndjson"),
+            reverts_path: dir.join("reverts.ndjson"),
+            lock: Mutex::new(()),
+        })
+    }
+
+    fn append_line(path: &Path, line: &str) -> Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{line}")?;
+        Ok(())
+    }
+
+    fn max_block_number(path: &Path) -> Result<Option<u64>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let reader = BufReader::new(std::fs::File::open(path)?);
+        let mut max_block = None;
+        for line in reader.lines() {
+            let line = line?;
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) {
+                if let Some(block_number) = value.get("block_number").and_then(|v| v.as_u64()) {
+                    max_block = max_block.max(Some(block_number));
+                }
+            }
+        }
+        Ok(max_block)
+    }
+}
+
+#[async_trait]
+impl Store for NdjsonStore {
+    async fn append_event(&self, event: &VaultEvent) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let record = EventRecord {
+            block_number: event.block_number,
+            log_index: event.log_index.to_string(),
+            event,
+        };
+        Self::append_line(&self.events_path, &serde_json::to_string(&record)?)
+    }
+
+    async fn upsert_state(&self, state: &VaultState) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let record = StateRecord {
+            block_number: state.block_number,
+            state,
+        };
+        Self::append_line(&self.state_path, &serde_json::to_string(&record)?)
+    }
+
+    async fn last_processed_block(&self) -> Result<Option<u64>> {
+        let events_max = Self::max_block_number(&self.events_path)?;
+        let state_max = Self::max_block_number(&self.state_path)?;
+        Ok(events_max.max(state_max))
+    }
+
+    async fn revert_block(&self, reverted: &RevertedBlock) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        Self::append_line(&self.reverts_path, &serde_json::to_string(reverted)?)
+    }
+}
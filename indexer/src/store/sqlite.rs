@@ -0,0 +1,140 @@
+use super::Store;
+use crate::{RevertedBlock, VaultEvent, VaultEventKind, VaultState};
+use anyhow::Result;
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::{path::Path, sync::Arc};
+use tokio::sync::Mutex;
+
+/// Embedded SQLite store. Unlike the NDJSON log, this lets downstream
+/// consumers query historical deposits/withdrawals and reconstruct a
+/// share-price (`rate`) time series directly with SQL, rather than scanning
+/// files.
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS events (
+                block_number     INTEGER NOT NULL,
+                log_index        TEXT    NOT NULL,
+                block_hash       TEXT    NOT NULL,
+                transaction_hash TEXT    NOT NULL,
+                kind             TEXT    NOT NULL,
+                payload          TEXT    NOT NULL,
+                PRIMARY KEY (block_number, log_index)
+            );
+            CREATE TABLE IF NOT EXISTS vault_state (
+                block_number INTEGER PRIMARY KEY,
+                block_hash   TEXT    NOT NULL,
+                total_assets TEXT    NOT NULL,
+                total_supply TEXT    NOT NULL,
+                timestamp    INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS reverted_blocks (
+                block_number INTEGER NOT NULL,
+                block_hash   TEXT    NOT NULL,
+                PRIMARY KEY (block_number, block_hash)
+            );
+            ",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn kind_label(kind: &VaultEventKind) -> &'static str {
+        match kind {
+            VaultEventKind::Deposit(_) => "Deposit",
+            VaultEventKind::Withdraw(_) => "Withdraw",
+            VaultEventKind::Transfer(_) => "Transfer",
+        }
+    }
+}
+
+#[async_trait]
+impl Store for SqliteStore {
+    async fn append_event(&self, event: &VaultEvent) -> Result<()> {
+        let kind = Self::kind_label(&event.kind).to_string();
+        let payload = serde_json::to_string(&event.kind)?;
+        let (block_number, log_index, block_hash, tx_hash) = (
+            event.block_number,
+            event.log_index.to_string(),
+            format!("{:?}", event.block_hash),
+            format!("{:?}", event.transaction_hash),
+        );
+
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            conn.blocking_lock().execute(
+                "INSERT OR REPLACE INTO events (block_number, log_index, block_hash, transaction_hash, kind, payload)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![block_number, log_index, block_hash, tx_hash, kind, payload],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    async fn upsert_state(&self, state: &VaultState) -> Result<()> {
+        let (block_number, block_hash, total_assets, total_supply, timestamp) = (
+            state.block_number,
+            format!("{:?}", state.block_hash),
+            state.total_assets.to_string(),
+            state.total_supply.to_string(),
+            state.timestamp,
+        );
+
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            conn.blocking_lock().execute(
+                "INSERT INTO vault_state (block_number, block_hash, total_assets, total_supply, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(block_number) DO UPDATE SET
+                    block_hash = excluded.block_hash,
+                    total_assets = excluded.total_assets,
+                    total_supply = excluded.total_supply,
+                    timestamp = excluded.timestamp",
+                params![block_number, block_hash, total_assets, total_supply, timestamp],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    async fn last_processed_block(&self) -> Result<Option<u64>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || -> Result<Option<u64>> {
+            let conn = conn.blocking_lock();
+            let events_max: Option<i64> = conn
+                .query_row("SELECT MAX(block_number) FROM events", [], |row| row.get(0))
+                .optional()?
+                .flatten();
+            let state_max: Option<i64> = conn
+                .query_row("SELECT MAX(block_number) FROM vault_state", [], |row| row.get(0))
+                .optional()?
+                .flatten();
+            Ok(events_max.max(state_max).map(|n| n as u64))
+        })
+        .await?
+    }
+
+    async fn revert_block(&self, reverted: &RevertedBlock) -> Result<()> {
+        let (block_number, block_hash) = (reverted.block_number, format!("{:?}", reverted.block_hash));
+
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            conn.blocking_lock().execute(
+                "INSERT OR REPLACE INTO reverted_blocks (block_number, block_hash) VALUES (?1, ?2)",
+                params![block_number, block_hash],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+}
@@ -0,0 +1,114 @@
+use ethers::core::types::{Filter, FilterBlockOption, Log, U256};
+use ethers::providers::Middleware;
+use futures::{stream, Stream};
+use std::{sync::Arc, time::Duration};
+
+/// Polls `eth_getFilterChanges` on a server-side filter as a fallback for
+/// endpoints that don't support `eth_subscribe` (plain HTTP, or WS providers
+/// that drop subscriptions on reconnect).
+///
+/// If the node reports the filter has expired ("filter not found"), a new
+/// filter is installed and logs between the last processed block and the
+/// current one are replayed via `eth_getLogs` so nothing is missed across the
+/// gap.
+pub struct FilterPoller<M> {
+    provider: Arc<M>,
+    filter: Filter,
+    poll_interval: Duration,
+}
+
+struct PollerState<M> {
+    provider: Arc<M>,
+    filter: Filter,
+    poll_interval: Duration,
+    filter_id: Option<U256>,
+    last_processed_block: u64,
+}
+
+impl<M: Middleware + 'static> FilterPoller<M> {
+    pub fn new(provider: Arc<M>, filter: Filter, poll_interval: Duration) -> Self {
+        Self {
+            provider,
+            filter,
+            poll_interval,
+        }
+    }
+
+    /// Turns this poller into a stream of log batches, starting from
+    /// `from_block` (used to backfill any gap after a filter expires).
+    pub fn into_stream(self, from_block: u64) -> impl Stream<Item = Vec<Log>> {
+        let state = PollerState {
+            provider: self.provider,
+            filter: self.filter,
+            poll_interval: self.poll_interval,
+            filter_id: None,
+            last_processed_block: from_block,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                tokio::time::sleep(state.poll_interval).await;
+
+                let filter_id = match state.filter_id {
+                    Some(id) => id,
+                    None => match state.provider.new_filter(ethers::types::FilterKind::Logs(&state.filter)).await {
+                        Ok(id) => {
+                            state.filter_id = Some(id);
+                            id
+                        }
+                        Err(err) => {
+                            log::warn!("failed to install log filter, retrying: {err}");
+                            continue;
+                        }
+                    },
+                };
+
+                match state.provider.get_filter_changes::<_, Log>(filter_id).await {
+                    Ok(logs) => {
+                        if let Some(max_block) = logs.iter().filter_map(|l| l.block_number).max() {
+                            state.last_processed_block = max_block.as_u64();
+                        }
+                        return Some((logs, state));
+                    }
+                    Err(err) if is_filter_not_found(&err) => {
+                        log::warn!("log filter expired, recreating and replaying from block {}", state.last_processed_block);
+                        // Install the replacement filter before replaying, so
+                        // blocks mined while the replay runs are reported by it.
+                        state.filter_id = state
+                            .provider
+                            .new_filter(ethers::types::FilterKind::Logs(&state.filter))
+                            .await
+                            .ok();
+
+                        let mut replay_filter = state.filter.clone();
+                        replay_filter.block_option = FilterBlockOption::Range {
+                            from_block: Some((state.last_processed_block + 1).into()),
+                            to_block: None,
+                        };
+
+                        match state.provider.get_logs(&replay_filter).await {
+                            Ok(logs) => {
+                                if let Some(max_block) = logs.iter().filter_map(|l| l.block_number).max() {
+                                    state.last_processed_block = max_block.as_u64();
+                                }
+                                return Some((logs, state));
+                            }
+                            Err(err) => {
+                                log::warn!("failed to replay logs after filter expiry: {err}");
+                                continue;
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        log::warn!("eth_getFilterChanges failed, retrying: {err}");
+                        continue;
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn is_filter_not_found<E: std::fmt::Display>(err: &E) -> bool {
+    err.to_string().to_lowercase().contains("filter not found")
+}
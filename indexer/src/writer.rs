@@ -0,0 +1,58 @@
+use crate::Vault;
+use anyhow::Result;
+use ethers::{
+    middleware::{
+        gas_oracle::{GasOracleMiddleware, GeoMeanGasOracle},
+        NonceManagerMiddleware, SignerMiddleware,
+    },
+    providers::{Http, Middleware, Provider},
+    signers::LocalWallet,
+    types::{Address, TxHash, U256},
+};
+use std::sync::Arc;
+
+/// The middleware stack used for the write path: a signer (for `eth_sendTransaction`),
+/// wrapped in automatic nonce management, wrapped in gas price estimation.
+/// Built once in `main` and shared by every write call.
+pub type WriteMiddleware =
+    GasOracleMiddleware<NonceManagerMiddleware<SignerMiddleware<Provider<Http>, LocalWallet>>, GeoMeanGasOracle>;
+
+/// Submits vault-maintenance transactions (`harvest`, `rebalance`, `redeem`)
+/// on top of a signer-backed middleware stack, which supplies automatic
+/// nonce management and gas estimation. Indexing stays read-only when no
+/// writer is configured.
+pub struct VaultWriter<M> {
+    contract: Vault<M>,
+}
+
+impl<M: Middleware + 'static> VaultWriter<M> {
+    pub fn new(vault_address: Address, middleware: Arc<M>) -> Self {
+        Self {
+            contract: Vault::new(vault_address, middleware),
+        }
+    }
+
+    /// Collects and compounds yield.
+    pub async fn harvest(&self) -> Result<TxHash> {
+        let pending = self.contract.harvest().send().await?;
+        Ok(pending.tx_hash())
+    }
+
+    /// Moves funds between strategies to rebalance the vault.
+    pub async fn rebalance(&self) -> Result<TxHash> {
+        let pending = self.contract.rebalance().send().await?;
+        Ok(pending.tx_hash())
+    }
+
+    /// Redeems `shares` on behalf of `owner`, sending the underlying assets
+    /// to `receiver`. Unlike `harvest`/`rebalance`, a redemption is scoped to
+    /// a specific owner's share balance rather than the aggregate indexed
+    /// state, so it isn't triggered automatically from `VaultIndexer` — it's
+    /// here for an operator-facing call site (e.g. a CLI or RPC control
+    /// surface) to drive directly.
+    #[allow(dead_code)]
+    pub async fn redeem(&self, shares: U256, receiver: Address, owner: Address) -> Result<TxHash> {
+        let pending = self.contract.redeem(shares, receiver, owner).send().await?;
+        Ok(pending.tx_hash())
+    }
+}
@@ -2,67 +2,39 @@ use anyhow::Result;
 use ethers::{
     abi::Abi,
     contract::Contract,
-    providers::{Middleware, Provider, StreamExt, Ws},
-    types::{Address, BlockNumber, Filter, U256},
+    providers::{Middleware, PubsubClient, StreamExt},
+    types::{Address, BlockNumber, Filter, TxHash, U256},
 };
 use std::sync::Arc;
-use vault_indexer_core::{VaultConfig, VaultState};
+use vault_indexer_core::VaultState;
 
-pub struct VaultIndexer {
-    provider: Arc<Provider<Ws>>,
-    contract: Contract<Provider<Ws>>,
+/// Indexes vault state and, when the underlying middleware stack includes a
+/// signer, can also submit vault-maintenance transactions.
+///
+/// Generic over `M: Middleware` (rather than hard-coded to `Provider<Ws>`) so
+/// callers can wrap the base provider in `SignerMiddleware`,
+/// `NonceManagerMiddleware`, and `GasOracleMiddleware` to get automatic
+/// nonce management and gas estimation for the write path. A read-only stack
+/// (plain `Provider<Ws>`, no signer) still works for indexing; only the write
+/// methods require a signer further down the stack. `run` is only available
+/// when the bottom of the stack is a pubsub transport, so an HTTP-only stack
+/// has no indexing loop here.
+pub struct VaultIndexer<M> {
+    provider: Arc<M>,
+    contract: Contract<M>,
 }
 
-impl VaultIndexer {
-    pub async fn new(config: VaultConfig) -> Result<Self> {
-        let provider = Arc::new(Provider::<Ws>::connect(&config.rpc_url).await?);
-        
-        // Load contract ABI
+impl<M: Middleware + 'static> VaultIndexer<M> {
+    pub fn new(provider: Arc<M>, vault_address: Address) -> Result<Self> {
         let abi: Abi = serde_json::from_str(include_str!("../../../abi/vault.json"))?;
-        let contract = Contract::new(config.vault_address, abi, provider.clone());
+        let contract = Contract::new(vault_address, abi, provider.clone());
 
-        Ok(Self {
-            provider,
-            contract,
-        })
-    }
-
-    pub async fn run(&self) -> Result<()> {
-        // Get and print initial state
-        let initial_state = self.get_vault_state(BlockNumber::Latest).await?;
-        println!("Initial state: {:#?}", initial_state);
-
-        // Subscribe to relevant events
-        let filter = Filter::new()
-            .address(self.contract.address())
-            .event("Transfer") // Add other relevant events if needed
-            .from_block(BlockNumber::Latest);
-            
-        let mut event_stream = self.provider.subscribe_logs(&filter).await?;
-
-        println!("Listening for events...");
-        
-        while let Some(log) = event_stream.next().await {
-            match log {
-                Ok(log) => {
-                    let block_number = log.block_number.unwrap();
-                    println!("\nNew event at block {}", block_number);
-                    
-                    // Get and print updated state
-                    if let Ok(new_state) = self.get_vault_state(block_number).await {
-                        println!("Updated state: {:#?}", new_state);
-                    }
-                }
-                Err(e) => println!("Error processing log: {:?}", e),
-            }
-        }
-
-        Ok(())
+        Ok(Self { provider, contract })
     }
 
     async fn get_vault_state(&self, block: impl Into<BlockNumber>) -> Result<VaultState> {
         let block = block.into();
-        
+
         // Call contract methods to get current state
         let total_assets: U256 = self.contract.method("totalAssets", ())?.call().await?;
         let total_supply: U256 = self.contract.method("totalSupply", ())?.call().await?;
@@ -70,10 +42,10 @@ impl VaultIndexer {
         let asset_address: Address = self.contract.method("asset", ())?.call().await?;
         let atoken_address: Address = self.contract.method("aToken", ())?.call().await?;
         let reward_tokens: Vec<Address> = self.contract.method("rewardTokens", ())?.call().await?;
-        
+
         let block_details = self.provider.get_block(block).await?
             .expect("Block not found");
-        
+
         Ok(VaultState {
             block_number: block_details.number.unwrap().as_u64(),
             timestamp: block_details.timestamp.as_u64(),
@@ -85,4 +57,63 @@ impl VaultIndexer {
             reward_tokens,
         })
     }
-} 
\ No newline at end of file
+
+    /// Calls the vault's `harvest()` to collect and compound yield. Requires
+    /// a signer in the middleware stack.
+    pub async fn harvest(&self) -> Result<TxHash> {
+        let pending = self.contract.method::<_, ()>("harvest", ())?.send().await?;
+        Ok(pending.tx_hash())
+    }
+
+    /// Calls the vault's `rebalance()` to move funds between strategies.
+    /// Requires a signer in the middleware stack.
+    pub async fn rebalance(&self) -> Result<TxHash> {
+        let pending = self.contract.method::<_, ()>("rebalance", ())?.send().await?;
+        Ok(pending.tx_hash())
+    }
+
+    /// Redeems `shares` from the vault on behalf of `owner`, sending the
+    /// underlying assets to `receiver`. Requires a signer in the middleware
+    /// stack.
+    pub async fn redeem(&self, shares: U256, receiver: Address, owner: Address) -> Result<TxHash> {
+        let pending = self
+            .contract
+            .method::<_, U256>("redeem", (shares, receiver, owner))?
+            .send()
+            .await?;
+        Ok(pending.tx_hash())
+    }
+}
+
+impl<M: Middleware + 'static> VaultIndexer<M>
+where
+    M::Provider: PubsubClient,
+{
+    pub async fn run(&self) -> Result<()> {
+        // Get and print initial state
+        let initial_state = self.get_vault_state(BlockNumber::Latest).await?;
+        println!("Initial state: {:#?}", initial_state);
+
+        // Subscribe to relevant events
+        let filter = Filter::new()
+            .address(self.contract.address())
+            .event("Transfer") // Add other relevant events if needed
+            .from_block(BlockNumber::Latest);
+
+        let mut event_stream = self.provider.subscribe_logs(&filter).await?;
+
+        println!("Listening for events...");
+
+        while let Some(log) = event_stream.next().await {
+            let block_number = log.block_number.unwrap();
+            println!("\nNew event at block {}", block_number);
+
+            // Get and print updated state
+            if let Ok(new_state) = self.get_vault_state(block_number).await {
+                println!("Updated state: {:#?}", new_state);
+            }
+        }
+
+        Ok(())
+    }
+}